@@ -0,0 +1,74 @@
+use std::{env, fs, path::Path};
+
+use shaderc::{CompileOptions, Compiler, EnvVersion, ShaderKind, TargetEnv};
+
+// Shared with the runtime GLSL fallback path (`create_shader` in src/gllcms.rs) so the prefix
+// pre-validated here can never drift from what's actually compiled at runtime.
+include!("src/shader_prefix.rs");
+
+// 430 lines up with the GLSL the rest of the element family already targets; kept in sync with
+// the `GLSLVersion::_430` passed to `create_stage` in src/gllcms.rs.
+const GLSL_VERSION: &str = "430";
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/shaders/vertex.glsl");
+    println!("cargo:rerun-if-changed=src/shaders/fragment.glsl");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set by cargo");
+    let out_dir = Path::new(&out_dir);
+
+    let mut compiler = Compiler::new().expect("Failed to create shaderc compiler");
+
+    // These shaders are only ever consumed via `GL_ARB_gl_spirv`/`glSpecializeShader`, which
+    // expects SPIR-V for the OpenGL execution environment, not Vulkan's (shaderc's default).
+    // Vulkan semantics also require explicit `layout(binding=N)` on every opaque uniform, which
+    // `tex`/`lut3d` in fragment.glsl don't have, so leaving this unset fails the build outright.
+    let mut options = CompileOptions::new().expect("Failed to create shaderc compile options");
+    options.set_target_env(TargetEnv::OpenGL, EnvVersion::OpenGL4_5 as u32);
+
+    compile_stage(
+        &mut compiler,
+        &options,
+        out_dir,
+        "vertex.spv",
+        "vertex.glsl",
+        &format!(
+            "{}{}",
+            vertex_prefix(GLSL_VERSION),
+            include_str!("src/shaders/vertex.glsl")
+        ),
+        ShaderKind::Vertex,
+    );
+    compile_stage(
+        &mut compiler,
+        &options,
+        out_dir,
+        "fragment.spv",
+        "fragment.glsl",
+        &format!(
+            "{}{}",
+            fragment_prefix(GLSL_VERSION),
+            include_str!("src/shaders/fragment.glsl")
+        ),
+        ShaderKind::Fragment,
+    );
+}
+
+// Fails the build on any compile/validation error, so a GLSL mistake is caught here instead
+// of surfacing as a runtime `vertex.compile().unwrap()` panic on whatever driver a user has.
+fn compile_stage(
+    compiler: &mut Compiler,
+    options: &CompileOptions<'_>,
+    out_dir: &Path,
+    out_file: &str,
+    input_name: &str,
+    source: &str,
+    kind: ShaderKind,
+) {
+    let artifact = compiler
+        .compile_into_spirv(source, kind, input_name, "main", Some(options))
+        .unwrap_or_else(|err| panic!("Failed to compile {input_name} to SPIR-V: {err}"));
+
+    fs::write(out_dir.join(out_file), artifact.as_binary_u8())
+        .unwrap_or_else(|err| panic!("Failed to write {out_file}: {err}"));
+}
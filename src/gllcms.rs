@@ -1,5 +1,10 @@
-use std::{convert::TryInto, sync::Mutex};
+use std::{
+    convert::TryInto,
+    hash::{Hash, Hasher},
+    sync::Mutex,
+};
 
+use glow::HasContext;
 use gst_gl::{
     gst::{glib, subclass::ElementMetadata},
     gst_base::subclass::{prelude::*, BaseTransformMode},
@@ -10,72 +15,45 @@ use gst_gl::{
 use lcms2::*;
 use once_cell::sync::Lazy;
 
-// Default vertex shader from gst_gl_shader_string_vertex_default
-const VERTEX_SHADER: &str = r"
-in vec4 a_position;
-in vec2 a_texcoord;
-out vec2 v_texcoord;
-void main()
-{
-   gl_Position = a_position;
-   v_texcoord = a_texcoord;
-}";
-
-const FRAGMENT_SHADER: &str = r"
-in vec2 v_texcoord;
-out vec4 fragColor;
-
-uniform sampler2D tex;
-layout(binding = 0)
-buffer lutTable
-{
-    int lut[];
+use crate::settings::{
+    build_transform, color_param_specs, color_property, set_color_property, Settings,
 };
-
-void main () {
-    vec4 rgba = texture(tex, v_texcoord);
-    if (v_texcoord.y > 0.5) {
-        fragColor = rgba;
-    } else {
-        vec4 rgb_ = vec4(rgba.xyz, 0);
-        uint idx = packUnorm4x8(rgb_);
-        vec3 rgb = unpackUnorm4x8(lut[idx]).xyz;
-        fragColor = vec4(rgb, 1);
-    }
-}
-";
-
-const DEFAULT_BRIGHTNESS: f64 = 0f64;
-const DEFAULT_CONTRAST: f64 = 1f64;
-const DEFAULT_HUE: f64 = 0f64;
-const DEFAULT_SATURATION: f64 = 0f64;
-
-#[derive(Debug, Clone, PartialEq)]
-struct Settings {
-    icc: Option<String>,
-    brightness: f64,
-    contrast: f64,
-    hue: f64,
-    saturation: f64,
+use crate::shader_prefix::{fragment_prefix, vertex_prefix, LUT_SIZE};
+
+// Default vertex shader from gst_gl_shader_string_vertex_default. Kept in its own file so
+// build.rs can feed the exact same source into shaderc for build-time validation.
+const VERTEX_SHADER: &str = include_str!("shaders/vertex.glsl");
+const FRAGMENT_SHADER: &str = include_str!("shaders/fragment.glsl");
+
+// Pre-validated SPIR-V for the above, compiled by build.rs; see `create_stage`.
+const VERTEX_SPIRV: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/vertex.spv"));
+const FRAGMENT_SPIRV: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/fragment.spv"));
+
+// Double-buffered `GL_TIME_ELAPSED` queries for one timed section: this frame's pair index is
+// read back on the *next* call to `filter_texture`, by which point the GPU has usually finished
+// it, so `glGetQueryObjectui64v` rarely has to stall the pipeline waiting on the result.
+struct GpuTimer {
+    queries: [glow::NativeQuery; 2],
+    // Whether `begin_query`/`end_query` has been issued for each parity slot since that slot
+    // was last read back. Per the GL
+    // spec a name returned by `create_query` only becomes a query object on its first
+    // `BeginQuery`, so the readback below must skip a slot that hasn't been issued yet (e.g.
+    // `lut_rebuild`'s slot when settings haven't changed since it was last built).
+    issued: [bool; 2],
 }
 
-impl Default for Settings {
-    fn default() -> Self {
-        Self {
-            icc: None,
-            brightness: DEFAULT_BRIGHTNESS,
-            contrast: DEFAULT_CONTRAST,
-            hue: DEFAULT_HUE,
-            saturation: DEFAULT_SATURATION,
-        }
-    }
+struct GpuTimers {
+    lut_rebuild: GpuTimer,
+    render: GpuTimer,
 }
 
 struct State {
     shader: GLShader,
-    gl: gl::Gl,
-    lut_buffer: gl::types::GLuint,
+    gl: glow::Context,
+    lut_texture: glow::NativeTexture,
     current_settings: Option<Settings>,
+    gpu_timers: Option<GpuTimers>,
+    frame_index: u64,
 }
 
 #[derive(Default)]
@@ -83,44 +61,22 @@ pub struct GlLcms {
     // TODO: Need multi-reader lock?
     settings: Mutex<Settings>,
     state: Mutex<Option<State>>,
+    last_gpu_time_ns: std::sync::atomic::AtomicU64,
 }
 
-static PROPERTIES: Lazy<[glib::ParamSpec; 5]> = Lazy::new(|| {
+static PROPERTIES: Lazy<[glib::ParamSpec; 6]> = Lazy::new(|| {
+    let [icc, brightness, contrast, hue, saturation] = color_param_specs();
     [
-        glib::ParamSpecString::builder("icc")
-            .nick("ICC Profile")
-            .blurb("Path to ICC color profile")
-            .build(),
-        glib::ParamSpecDouble::builder("brightness")
-            .nick("Bright")
-            .blurb("Extra brightness correction")
-            // TODO: Docs don't clarify min and max!
-            .minimum(f64::MIN)
-            .maximum(f64::MAX)
-            .default_value(DEFAULT_BRIGHTNESS)
-            .build(),
-        glib::ParamSpecDouble::builder("contrast")
-            .nick("Contrast")
-            .blurb("Extra contrast correction")
-            // TODO: Docs don't clarify min and max!
-            .minimum(f64::MIN)
-            .maximum(f64::MAX)
-            .default_value(DEFAULT_CONTRAST)
-            .build(),
-        glib::ParamSpecDouble::builder("hue")
-            .nick("Hue")
-            .blurb("Extra hue displacement in degrees")
-            .minimum(0f64)
-            .maximum(360f64)
-            .default_value(DEFAULT_HUE)
-            .build(),
-        glib::ParamSpecDouble::builder("saturation")
-            .nick("Saturation")
-            .blurb("Extra saturation correction")
-            // TODO: Docs don't clarify min and max!
-            .minimum(f64::MIN)
-            .maximum(f64::MAX)
-            .default_value(DEFAULT_SATURATION)
+        icc,
+        brightness,
+        contrast,
+        hue,
+        saturation,
+        glib::ParamSpecUInt64::builder("last-gpu-time-ns")
+            .nick("Last GPU Time")
+            .blurb("GPU time spent on the last render, in nanoseconds (0 if GL_ARB_timer_query is unsupported)")
+            .default_value(0)
+            .read_only()
             .build(),
         // TODO: Model white balance src+dest as structure
         // glib::ParamSpec::new_value_array(
@@ -169,34 +125,27 @@ impl ObjectImpl for GlLcms {
 
         let mut settings = self.settings.lock().unwrap();
 
-        match pspec.name() {
-            "icc" => settings.icc = value.get().expect("Type mismatch"),
-            "brightness" => settings.brightness = value.get().expect("Type mismatch"),
-            "contrast" => settings.contrast = value.get().expect("Type mismatch"),
-            "hue" => settings.hue = value.get().expect("Type mismatch"),
-            "saturation" => settings.saturation = value.get().expect("Type mismatch"),
-            _ => {
-                // This means someone added a property to PROPERTIES but forgot to handle it here...
-                gst::error!(CAT, imp: self, "Can't handle {:?}", pspec);
-                panic!("set_property unhandled for {:?}", pspec);
-            }
+        if !set_color_property(&mut settings, pspec, value) {
+            // This means someone added a property to PROPERTIES but forgot to handle it here...
+            gst::error!(CAT, imp: self, "Can't handle {:?}", pspec);
+            panic!("set_property unhandled for {:?}", pspec);
         }
     }
 
     fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        if pspec.name() == "last-gpu-time-ns" {
+            return self
+                .last_gpu_time_ns
+                .load(std::sync::atomic::Ordering::Relaxed)
+                .to_value();
+        }
+
         let settings = self.settings.lock().unwrap();
 
-        match pspec.name() {
-            "icc" => settings.icc.to_value(),
-            "brightness" => settings.brightness.to_value(),
-            "contrast" => settings.contrast.to_value(),
-            "hue" => settings.hue.to_value(),
-            "saturation" => settings.saturation.to_value(),
-            _ => {
-                gst::error!(CAT, imp: self, "Can't handle {:?}", pspec);
-                panic!("get_property unhandled for {:?}", pspec);
-            }
-        }
+        color_property(&settings, pspec).unwrap_or_else(|| {
+            gst::error!(CAT, imp: self, "Can't handle {:?}", pspec);
+            panic!("get_property unhandled for {:?}", pspec);
+        })
     }
 }
 
@@ -223,20 +172,175 @@ impl BaseTransformImpl for GlLcms {
     const TRANSFORM_IP_ON_PASSTHROUGH: bool = false;
 }
 
-fn create_shader(imp: &GlLcms, context: &GLContext) -> GLShader {
+// The `gst_gl` `GLShader` allocates its GL program eagerly in `GLShader::new`, so its handle
+// is valid for `glProgramBinary`/`glGetProgramBinary` both before and after linking.
+//
+// TODO: on a cache hit (see `try_link_cached_binary`) we never call `attach_unlocked`/`link()`,
+// only `glProgramBinary` directly on this handle. That's only correct if `GLShader` has no
+// internal "is linked" bookkeeping that `use_()` depends on later in `filter_texture`. Verify
+// this against the `gst_gl` C/Rust source (`gst_gl_shader_use`/`GstGLShaderPrivate`) before
+// relying on the cache path in production, or add a test that runs `gl_start`/`gl_stop` twice
+// to exercise it.
+fn gl_program(shader: &GLShader) -> glow::NativeProgram {
+    glow::NativeProgram(
+        std::num::NonZeroU32::new(shader.program_handle().try_into().unwrap())
+            .expect("GLShader should have allocated a GL program handle"),
+    )
+}
+
+// `GLSLStage::new` likewise allocates its GL shader object eagerly, before any source is
+// attached or compiled, so its handle is valid for `glShaderBinary`/`glSpecializeShader`.
+fn gl_shader(stage: &GLSLStage) -> glow::NativeShader {
+    glow::NativeShader(
+        std::num::NonZeroU32::new(stage.shader_handle().try_into().unwrap())
+            .expect("GLSLStage should have allocated a GL shader handle"),
+    )
+}
+
+// Prefer loading the `build.rs`-validated SPIR-V for `kind` when the driver advertises
+// `GL_ARB_gl_spirv`; fall back to the plain GLSL source path otherwise, or if specialization
+// is rejected at runtime despite the extension being present.
+#[allow(clippy::too_many_arguments)]
+fn create_stage(
+    imp: &GlLcms,
+    context: &GLContext,
+    gl: &glow::Context,
+    kind: u32,
+    version: GLSLVersion,
+    profile: GLSLProfile,
+    glsl_parts: &[&str],
+    spirv: &[u8],
+    spirv_supported: bool,
+) -> GLSLStage {
+    if spirv_supported {
+        let stage = GLSLStage::new(context, kind);
+        let shader = gl_shader(&stage);
+        unsafe { gl.shader_binary(&[shader], glow::SHADER_BINARY_FORMAT_SPIR_V, spirv) };
+        unsafe { gl.specialize_shader(shader, "main", &[], &[]) };
+
+        if unsafe { gl.get_shader_compile_status(shader) } {
+            gst::trace!(CAT, imp: imp, "Loaded pre-validated SPIR-V for {:?}", stage);
+            return stage;
+        }
+
+        gst::debug!(
+            CAT,
+            imp: imp,
+            "SPIR-V specialization rejected for {:?}, falling back to GLSL source",
+            stage
+        );
+    }
+
+    let stage = GLSLStage::with_strings(context, kind, version, profile, glsl_parts);
+    stage.compile().unwrap();
+    stage
+}
+
+// Keyed on the shader sources plus the driver/GPU identity: program binaries are only
+// guaranteed to load back on the exact same GL stack that produced them.
+fn program_cache_key(gl: &glow::Context, vertex_prefix: &str, fragment_prefix: &str) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    vertex_prefix.hash(&mut hasher);
+    fragment_prefix.hash(&mut hasher);
+    VERTEX_SHADER.hash(&mut hasher);
+    FRAGMENT_SHADER.hash(&mut hasher);
+    for name in [glow::VENDOR, glow::RENDERER, glow::VERSION] {
+        unsafe { gl.get_parameter_string(name) }.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn program_cache_path(key: u64) -> Option<std::path::PathBuf> {
+    let mut dir = dirs::cache_dir()?;
+    dir.push(env!("CARGO_PKG_NAME"));
+    Some(dir.join(format!("{key:016x}.bin")))
+}
+
+// Cache file layout: a little-endian `binaryFormat` GLenum, followed by the raw binary blob.
+fn load_cached_program_binary(path: &std::path::Path) -> Option<(u32, Vec<u8>)> {
+    let bytes = std::fs::read(path).ok()?;
+    if bytes.len() < 4 {
+        return None;
+    }
+    let (format_bytes, binary) = bytes.split_at(4);
+    let format = u32::from_le_bytes(format_bytes.try_into().unwrap());
+    Some((format, binary.to_vec()))
+}
+
+fn store_cached_program_binary(
+    path: &std::path::Path,
+    format: u32,
+    binary: &[u8],
+) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut bytes = Vec::with_capacity(4 + binary.len());
+    bytes.extend_from_slice(&format.to_le_bytes());
+    bytes.extend_from_slice(binary);
+    std::fs::write(path, bytes)
+}
+
+// Try to relink `shader`'s (already allocated, empty) program from a cached binary. Returns
+// `Err` with a human-readable reason on any mismatch, leaving `shader` untouched so the caller
+// can fall back to the full GLSL compile path.
+fn try_link_cached_binary(
+    imp: &GlLcms,
+    shader: &GLShader,
+    gl: &glow::Context,
+    format: u32,
+    binary: &[u8],
+) -> Result<(), &'static str> {
+    let program = gl_program(shader);
+    unsafe { gl.program_binary(program, format, binary) };
+    if unsafe { gl.get_program_link_status(program) } {
+        gst::trace!(
+            CAT,
+            imp: imp,
+            "Linked program {program:?} from {} byte cached binary (format {format:#x})",
+            binary.len()
+        );
+        Ok(())
+    } else {
+        Err("GL_LINK_STATUS false after glProgramBinary")
+    }
+}
+
+fn create_shader(imp: &GlLcms, context: &GLContext, gl: &glow::Context) -> GLShader {
     let shader = GLShader::new(context);
-    // 400 For (un)packUnorm
-    // 430 for SSBO (https://www.khronos.org/opengl/wiki/Shader_Storage_Buffer_Object)
+    // 430 lines up with the GLSL the rest of the element family already targets
     let version = GLSLVersion::_430;
     let profile = GLSLProfile::empty();
-    let shader_version = format!(
-        "#version {}",
-        &GLSLVersion::profile_to_string(version, profile).unwrap()
-    );
+    let version_str = GLSLVersion::profile_to_string(version, profile).unwrap();
+    let vertex_shader_prefix = vertex_prefix(&version_str);
+    let fragment_shader_prefix = fragment_prefix(&version_str);
+
+    let cache_path = program_cache_path(program_cache_key(
+        gl,
+        &vertex_shader_prefix,
+        &fragment_shader_prefix,
+    ));
+
+    if let Some(path) = &cache_path {
+        if let Some((format, binary)) = load_cached_program_binary(path) {
+            match try_link_cached_binary(imp, &shader, gl, format, &binary) {
+                Ok(()) => return shader,
+                Err(reason) => gst::debug!(
+                    CAT,
+                    imp: imp,
+                    "Rejecting cached program binary at {}: {reason}, recompiling",
+                    path.display()
+                ),
+            }
+        }
+    }
+
+    let spirv_supported = gl.supported_extensions().contains("GL_ARB_gl_spirv");
 
     // let vertex = GLSLStage::new_default_vertex(context);
     // new_default_vertex assumes GLSLVersion::None and ES | COMPATIBILITY profile
-    let shader_parts = [&shader_version, VERTEX_SHADER];
+    let shader_parts = [&vertex_shader_prefix, VERTEX_SHADER];
 
     gst::debug!(
         CAT,
@@ -245,12 +349,20 @@ fn create_shader(imp: &GlLcms, context: &GLContext) -> GLShader {
         &shader_parts
     );
 
-    let vertex =
-        GLSLStage::with_strings(context, gl::VERTEX_SHADER, version, profile, &shader_parts);
-    vertex.compile().unwrap();
+    let vertex = create_stage(
+        imp,
+        context,
+        gl,
+        glow::VERTEX_SHADER,
+        version,
+        profile,
+        &shader_parts,
+        VERTEX_SPIRV,
+        spirv_supported,
+    );
     shader.attach_unlocked(&vertex).unwrap();
 
-    let shader_parts = [&shader_version, FRAGMENT_SHADER];
+    let shader_parts = [&fragment_shader_prefix, FRAGMENT_SHADER];
 
     gst::debug!(
         CAT,
@@ -259,28 +371,92 @@ fn create_shader(imp: &GlLcms, context: &GLContext) -> GLShader {
         &shader_parts
     );
 
-    let fragment = GLSLStage::with_strings(
+    let fragment = create_stage(
+        imp,
         context,
-        gl::FRAGMENT_SHADER,
+        gl,
+        glow::FRAGMENT_SHADER,
         version,
         profile,
         &shader_parts,
+        FRAGMENT_SPIRV,
+        spirv_supported,
     );
-    fragment.compile().unwrap();
     shader.attach_unlocked(&fragment).unwrap();
     shader.link().unwrap();
 
     gst::debug!(CAT, imp: imp, "Successfully linked {:?}", shader);
 
+    if let Some(path) = &cache_path {
+        let program = gl_program(&shader);
+        let (format, binary) = unsafe { gl.get_program_binary(program) };
+        if let Err(err) = store_cached_program_binary(path, format, &binary) {
+            gst::warning!(CAT, imp: imp, "Failed to write program binary cache: {err}");
+        }
+    }
+
     shader
 }
 
-fn create_ssbo(gl: &gl::Gl) -> u32 {
-    let mut ssbo = std::mem::MaybeUninit::uninit();
-    unsafe {
-        gl.GenBuffers(1, ssbo.as_mut_ptr());
-        ssbo.assume_init()
+fn create_lut_texture(gl: &glow::Context) -> glow::NativeTexture {
+    unsafe { gl.create_texture() }.expect("Failed to create LUT texture")
+}
+
+fn create_gpu_timers(imp: &GlLcms, gl: &glow::Context) -> Option<GpuTimers> {
+    if !gl.supported_extensions().contains("GL_ARB_timer_query") {
+        gst::debug!(
+            CAT,
+            imp: imp,
+            "GL_ARB_timer_query not supported, last-gpu-time-ns will stay at 0"
+        );
+        return None;
+    }
+
+    let new_timer = || GpuTimer {
+        queries: [
+            unsafe { gl.create_query() }.expect("Failed to create timer query"),
+            unsafe { gl.create_query() }.expect("Failed to create timer query"),
+        ],
+        issued: [false, false],
+    };
+
+    Some(GpuTimers {
+        lut_rebuild: new_timer(),
+        render: new_timer(),
+    })
+}
+
+// Runs `f` wrapped in a `GL_TIME_ELAPSED` query when timing is enabled for this section.
+fn gpu_timed<T>(
+    gl: &glow::Context,
+    query: Option<glow::NativeQuery>,
+    f: impl FnOnce(&glow::Context) -> T,
+) -> T {
+    if let Some(query) = query {
+        unsafe { gl.begin_query(glow::TIME_ELAPSED, query) };
+        let result = f(gl);
+        unsafe { gl.end_query(glow::TIME_ELAPSED) };
+        result
+    } else {
+        f(gl)
+    }
+}
+
+// Identity RGB grid of `size`^3 points, packed the same way the lcms2 `RGBA_8` pixel format
+// expects (R in the low byte, A left at 0). Laid out with R fastest, then G, then B, to match
+// the row-major `glTexImage3D` upload of a `(size, size, size)` texture.
+fn build_lut_grid(size: u32) -> Vec<u32> {
+    let scale = |c: u32| c * 255 / (size - 1);
+
+    let mut grid = Vec::with_capacity((size * size * size) as usize);
+    for b in 0..size {
+        for g in 0..size {
+            for r in 0..size {
+                grid.push(scale(r) | (scale(g) << 8) | (scale(b) << 16));
+            }
+        }
     }
+    grid
 }
 
 impl GLBaseFilterImpl for GlLcms {
@@ -291,25 +467,30 @@ impl GLBaseFilterImpl for GlLcms {
         let context = obj.context().unwrap();
         let mut state = self.state.lock().unwrap();
 
-        let shader = create_shader(self, &context);
-
         // TODO: Should perhaps use Gst types, even though they appear to implement more complex and unnecessary features like automatic CPU mapping/copying
-        let gl = gl::Gl::load_with(|fn_name| context.proc_address(fn_name) as _);
+        let gl =
+            unsafe { glow::Context::from_loader_function(|fn_name| context.proc_address(fn_name) as _) };
 
-        let lut_buffer = create_ssbo(&gl);
+        let shader = create_shader(self, &context, &gl);
+
+        let lut_texture = create_lut_texture(&gl);
 
         gst::trace!(
             CAT,
             imp: self,
-            "Created SSBO containing lut at {lut_buffer:?}"
+            "Created LUT texture at {lut_texture:?}"
         );
 
+        let gpu_timers = create_gpu_timers(self, &gl);
+
         let was_uninitialized = state
             .replace(State {
                 shader,
                 gl,
-                lut_buffer,
+                lut_texture,
                 current_settings: None,
+                gpu_timers,
+                frame_index: 0,
             })
             .is_none();
         assert!(
@@ -324,10 +505,28 @@ impl GLBaseFilterImpl for GlLcms {
         gst::debug!(CAT, imp: self, "gl_stop");
 
         let mut state = self.state.lock().unwrap();
-        let _ = state
+        let State {
+            gl,
+            lut_texture,
+            gpu_timers,
+            ..
+        } = state
             .take()
             .expect("State must have been initialized when calling gl_stop()");
 
+        unsafe { gl.delete_texture(lut_texture) };
+
+        if let Some(timers) = gpu_timers {
+            let queries = timers
+                .lut_rebuild
+                .queries
+                .into_iter()
+                .chain(timers.render.queries);
+            for query in queries {
+                unsafe { gl.delete_query(query) };
+            }
+        }
+
         self.parent_gl_stop()
     }
 }
@@ -350,10 +549,53 @@ impl GLFilterImpl for GlLcms {
         let State {
             shader,
             gl,
-            lut_buffer,
+            lut_texture,
             current_settings,
+            gpu_timers,
+            frame_index,
         } = state;
-        let lut_buffer = *lut_buffer;
+        let lut_texture = *lut_texture;
+
+        // Queries are double-buffered per section: this call uses `timer_idx`, while
+        // `prev_timer_idx` names the pair that was begun+ended a whole frame ago and is read
+        // back below.
+        let timer_idx = (*frame_index % 2) as usize;
+        let prev_timer_idx = 1 - timer_idx;
+
+        // If settings don't change for a while, `lut_rebuild`'s slot stops being re-issued and
+        // `issued` stays false for it: skip that readback rather than reading a query object
+        // that was never turned into one by a `BeginQuery` call (`GL_INVALID_OPERATION`).
+        if *frame_index >= 1 {
+            if let Some(timers) = gpu_timers {
+                if timers.lut_rebuild.issued[prev_timer_idx] {
+                    let lut_ns = unsafe {
+                        gl.get_query_parameter_u64(
+                            timers.lut_rebuild.queries[prev_timer_idx],
+                            glow::QUERY_RESULT,
+                        )
+                    };
+                    gst::trace!(CAT, imp: self, "GPU timings: lut rebuild {lut_ns} ns");
+                    timers.lut_rebuild.issued[prev_timer_idx] = false;
+                }
+
+                let render_ns = unsafe {
+                    gl.get_query_parameter_u64(
+                        timers.render.queries[prev_timer_idx],
+                        glow::QUERY_RESULT,
+                    )
+                };
+                gst::trace!(CAT, imp: self, "GPU timings: render {render_ns} ns");
+                self.last_gpu_time_ns
+                    .store(render_ns, std::sync::atomic::Ordering::Relaxed);
+            }
+        }
+
+        let lut_query = gpu_timers
+            .as_ref()
+            .map(|timers| timers.lut_rebuild.queries[timer_idx]);
+        let render_query = gpu_timers
+            .as_ref()
+            .map(|timers| timers.render.queries[timer_idx]);
 
         let settings = &*self.settings.lock().unwrap();
         if current_settings.as_ref() != Some(settings) {
@@ -374,95 +616,92 @@ impl GLFilterImpl for GlLcms {
 
             gst::info!(CAT, imp: self, "Creating LUT from {:?}", settings);
 
-            let mut profiles = vec![];
-
-            if let Some(icc) = &settings.icc {
-                let custom_profile = Profile::new_file(icc).unwrap();
-                profiles.push(custom_profile);
+            if let Some(timers) = gpu_timers.as_mut() {
+                timers.lut_rebuild.issued[timer_idx] = true;
             }
 
-            // TODO: Put these four settings in a separate struct for easy Default comparison and elision
-            let bcsh = Profile::new_bchsw_abstract_context(
-                GlobalContext::new(),
-                // Can't have more than 255 points... Is this per-axis (as it's rather slow)?
-                255,
-                settings.brightness,
-                settings.contrast,
-                settings.hue,
-                settings.saturation,
-                /* No color temperature support yet */ None,
-            )
-            .unwrap();
-            profiles.push(bcsh);
-
-            // Use sRGB as output profile, last in the chain
-            let output_profile = Profile::new_srgb();
-
-            // TODO: bcsh on its own breaks Transform construction
-
-            let t = if let [single_profile] = &profiles[..] {
-                Transform::new(
-                    single_profile,
-                    PixelFormat::RGBA_8,
-                    &output_profile,
-                    PixelFormat::RGBA_8,
-                    Intent::Perceptual,
-                )
-                .unwrap()
-            } else {
-                // Output profile is last in the chain
-                profiles.push(output_profile);
-
-                // Turn into vec of references
-                let profiles = profiles.iter().collect::<Vec<_>>();
-                Transform::new_multiprofile(
-                    &profiles,
-                    PixelFormat::RGBA_8,
-                    PixelFormat::RGBA_8,
-                    Intent::Perceptual,
-                    // TODO: Check all flags
-                    Flags::NO_NEGATIVES | Flags::KEEP_SEQUENCE,
-                )
-                .unwrap()
-            };
-
-            let mut source_pixels = (0..0x1_00_00_00).collect::<Vec<_>>();
-            t.transform_in_place(&mut source_pixels);
-
-            // Bind in SSBO slot and upload data
-            unsafe { gl.BindBuffer(gl::SHADER_STORAGE_BUFFER, lut_buffer) };
-            unsafe {
-                // BufferStorage to keep the buffer mutable, in contrast to BufferStorage
-                gl.BufferStorage(
-                    gl::SHADER_STORAGE_BUFFER,
-                    (source_pixels.len() * std::mem::size_of::<u32>())
-                        .try_into()
-                        .unwrap(),
-                    source_pixels.as_ptr().cast(),
-                    0,
-                )
-            };
+            gpu_timed(gl, lut_query, |gl| {
+                let t = build_transform(settings, PixelFormat::RGBA_8);
+
+                // Only transform the LUT_SIZE^3 grid points instead of the full 24-bit colour
+                // cube; the GPU fills in the gaps with hardware trilinear interpolation.
+                let mut grid_pixels = build_lut_grid(LUT_SIZE);
+                t.transform_in_place(&mut grid_pixels);
+
+                unsafe { gl.bind_texture(glow::TEXTURE_3D, Some(lut_texture)) };
+                unsafe {
+                    gl.tex_parameter_i32(
+                        glow::TEXTURE_3D,
+                        glow::TEXTURE_MIN_FILTER,
+                        glow::LINEAR as i32,
+                    );
+                    gl.tex_parameter_i32(
+                        glow::TEXTURE_3D,
+                        glow::TEXTURE_MAG_FILTER,
+                        glow::LINEAR as i32,
+                    );
+                    gl.tex_parameter_i32(
+                        glow::TEXTURE_3D,
+                        glow::TEXTURE_WRAP_S,
+                        glow::CLAMP_TO_EDGE as i32,
+                    );
+                    gl.tex_parameter_i32(
+                        glow::TEXTURE_3D,
+                        glow::TEXTURE_WRAP_T,
+                        glow::CLAMP_TO_EDGE as i32,
+                    );
+                    gl.tex_parameter_i32(
+                        glow::TEXTURE_3D,
+                        glow::TEXTURE_WRAP_R,
+                        glow::CLAMP_TO_EDGE as i32,
+                    );
+                }
+                unsafe {
+                    let grid_bytes = std::slice::from_raw_parts(
+                        grid_pixels.as_ptr().cast::<u8>(),
+                        std::mem::size_of_val(grid_pixels.as_slice()),
+                    );
+                    gl.tex_image_3d(
+                        glow::TEXTURE_3D,
+                        0,
+                        glow::RGBA8 as i32,
+                        LUT_SIZE as i32,
+                        LUT_SIZE as i32,
+                        LUT_SIZE as i32,
+                        0,
+                        glow::RGBA,
+                        glow::UNSIGNED_BYTE,
+                        Some(grid_bytes),
+                    )
+                };
+            });
 
             state.current_settings = Some(settings.clone());
         }
 
-        // Bind the shader in advance to be able to bind our storage buffer
-        shader.use_();
+        if let Some(timers) = gpu_timers.as_mut() {
+            timers.render.issued[timer_idx] = true;
+        }
 
-        // Actually bind the lut to `uint lut[];`
-        unsafe { gl.BindBuffer(gl::SHADER_STORAGE_BUFFER, lut_buffer) };
-        unsafe {
-            gl.BindBufferBase(
-                gl::SHADER_STORAGE_BUFFER,
-                /* binding 0 */ 0,
-                lut_buffer,
-            )
-        };
+        gpu_timed(gl, render_query, |gl| {
+            // Bind the shader in advance to be able to set our LUT sampler uniform
+            shader.use_();
 
-        obj.render_to_target_with_shader(input, output, shader);
+            // Actually bind the lut to `uniform sampler3D lut3d;`, on a unit distinct from the
+            // `tex` input sampler that GLFilter itself binds to unit 0
+            unsafe { gl.active_texture(glow::TEXTURE0 + 1) };
+            unsafe { gl.bind_texture(glow::TEXTURE_3D, Some(lut_texture)) };
+            shader.set_uniform_1i("lut3d", 1);
+
+            obj.render_to_target_with_shader(input, output, shader);
+
+            // Cleanup: unbind and restore unit 0 as active, since the GL context is shared with
+            // the rest of the pipeline and other elements assume it's active by default.
+            unsafe { gl.bind_texture(glow::TEXTURE_3D, None) };
+            unsafe { gl.active_texture(glow::TEXTURE0) };
+        });
 
-        // Cleanup
-        unsafe { gl.BindBuffer(gl::SHADER_STORAGE_BUFFER, 0) };
+        *frame_index += 1;
 
         gst::trace!(CAT, imp: self, "Render finished");
 
@@ -0,0 +1,203 @@
+use std::sync::Mutex;
+
+use gst::{glib, subclass::ElementMetadata};
+use gst_base::subclass::{prelude::*, BaseTransformMode};
+use gst_video::{prelude::*, subclass::prelude::*, VideoCapsBuilder, VideoFormat};
+use lcms2::{PixelFormat, Transform};
+use once_cell::sync::Lazy;
+
+use crate::settings::{
+    build_transform, color_param_specs, color_property, set_color_property, Settings,
+};
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "lcms",
+        gst::DebugColorFlags::empty(),
+        Some("Rust LCMS2-based color correction on the CPU"),
+    )
+});
+
+struct State {
+    format: PixelFormat,
+    transform: Option<Transform<u32, u32>>,
+    current_settings: Option<Settings>,
+    // Reused scratch row, since `Transform::transform_pixels` needs a source distinct from the
+    // destination but we only have one buffer to transform in place.
+    scratch: Vec<u32>,
+}
+
+#[derive(Default)]
+pub struct Lcms {
+    settings: Mutex<Settings>,
+    state: Mutex<Option<State>>,
+}
+
+static PROPERTIES: Lazy<[glib::ParamSpec; 5]> = Lazy::new(color_param_specs);
+
+#[glib::object_subclass]
+impl ObjectSubclass for Lcms {
+    const NAME: &'static str = "lcms";
+    type ParentType = gst_video::VideoFilter;
+    type Type = super::Lcms;
+}
+
+impl ObjectImpl for Lcms {
+    fn properties() -> &'static [glib::ParamSpec] {
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+        gst::info!(CAT, imp: self, "Changing {:?} to {:?}", pspec, value);
+
+        let mut settings = self.settings.lock().unwrap();
+
+        if !set_color_property(&mut settings, pspec, value) {
+            // This means someone added a property to PROPERTIES but forgot to handle it here...
+            gst::error!(CAT, imp: self, "Can't handle {:?}", pspec);
+            panic!("set_property unhandled for {:?}", pspec);
+        }
+    }
+
+    fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        let settings = self.settings.lock().unwrap();
+
+        color_property(&settings, pspec).unwrap_or_else(|| {
+            gst::error!(CAT, imp: self, "Can't handle {:?}", pspec);
+            panic!("get_property unhandled for {:?}", pspec);
+        })
+    }
+}
+
+impl GstObjectImpl for Lcms {}
+
+impl ElementImpl for Lcms {
+    fn metadata() -> Option<&'static ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<ElementMetadata> = Lazy::new(|| {
+            ElementMetadata::new(
+                "Rust LCMS2-based color correction",
+                "Filter/Effect/Converter/Video",
+                env!("CARGO_PKG_DESCRIPTION"),
+                env!("CARGO_PKG_AUTHORS"),
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            // Only the two formats `pixel_format_for` knows how to hand to lcms2.
+            let caps = VideoCapsBuilder::new()
+                .format_list([VideoFormat::Rgba, VideoFormat::Bgra])
+                .build();
+
+            vec![
+                gst::PadTemplate::new(
+                    "src",
+                    gst::PadDirection::Src,
+                    gst::PadPresence::Always,
+                    &caps,
+                )
+                .unwrap(),
+                gst::PadTemplate::new(
+                    "sink",
+                    gst::PadDirection::Sink,
+                    gst::PadPresence::Always,
+                    &caps,
+                )
+                .unwrap(),
+            ]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+}
+
+impl BaseTransformImpl for Lcms {
+    const MODE: BaseTransformMode = BaseTransformMode::AlwaysInPlace;
+    const PASSTHROUGH_ON_SAME_CAPS: bool = false;
+    const TRANSFORM_IP_ON_PASSTHROUGH: bool = false;
+}
+
+// The two pixel layouts `gst_video` negotiates that lcms2 also understands directly, so frames
+// can be transformed without an extra channel-swizzling pass.
+fn pixel_format_for(format: VideoFormat) -> Option<PixelFormat> {
+    match format {
+        VideoFormat::Rgba => Some(PixelFormat::RGBA_8),
+        VideoFormat::Bgra => Some(PixelFormat::BGRA_8),
+        _ => None,
+    }
+}
+
+impl VideoFilterImpl for Lcms {
+    fn set_info(
+        &self,
+        incaps: &gst::Caps,
+        in_info: &gst_video::VideoInfo,
+        outcaps: &gst::Caps,
+        out_info: &gst_video::VideoInfo,
+    ) -> Result<(), gst::LoggableError> {
+        let format = pixel_format_for(in_info.format()).ok_or_else(|| {
+            gst::loggable_error!(CAT, "Unsupported format {:?}", in_info.format())
+        })?;
+
+        *self.state.lock().unwrap() = Some(State {
+            format,
+            transform: None,
+            current_settings: None,
+            scratch: Vec::new(),
+        });
+
+        self.parent_set_info(incaps, in_info, outcaps, out_info)
+    }
+
+    fn transform_frame_ip(
+        &self,
+        frame: &mut gst_video::VideoFrameRef<&mut gst::BufferRef>,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let mut state = self.state.lock().unwrap();
+        let state = state
+            .as_mut()
+            .expect("Should not be calling transform_frame_ip() before set_info()");
+
+        let State {
+            format,
+            transform,
+            current_settings,
+            scratch,
+        } = state;
+
+        let settings = &*self.settings.lock().unwrap();
+        if current_settings.as_ref() != Some(settings) {
+            gst::trace!(CAT, imp: self, "Settings changed, rebuilding transform");
+            *transform = Some(build_transform(settings, *format));
+            *current_settings = Some(settings.clone());
+        }
+        let transform = transform.as_ref().expect("just created above");
+
+        let width = frame.width() as usize;
+        let stride = frame.plane_stride()[0] as usize;
+        scratch.resize(width, 0);
+
+        let data = frame.plane_data_mut(0).map_err(|_| gst::FlowError::Error)?;
+        for row in data.chunks_exact_mut(stride) {
+            // SAFETY: `align_to_mut` checks alignment itself and only hands back a non-empty
+            // `prefix` if the row isn't actually 4-byte aligned, which we check for below; u8 to
+            // u32 has no padding/validity concerns since every bit pattern of both is valid.
+            let (prefix, pixels, _) = unsafe { row[..width * 4].align_to_mut::<u32>() };
+            if !prefix.is_empty() {
+                gst::error!(
+                    CAT,
+                    imp: self,
+                    "Plane row is not 4-byte aligned, cannot transform in place"
+                );
+                return Err(gst::FlowError::Error);
+            }
+            scratch.copy_from_slice(pixels);
+            transform.transform_pixels(scratch, pixels);
+        }
+
+        Ok(gst::FlowSuccess::Ok)
+    }
+}
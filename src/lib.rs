@@ -71,16 +71,29 @@ gst::plugin_define!(
 );
 
 mod gllcms;
+mod lcms;
+mod settings;
+mod shader_prefix;
 
 glib::wrapper! {
     pub struct GlLcms(ObjectSubclass<gllcms::GlLcms>) @extends gst_gl::GLFilter, gst_gl::GLBaseFilter;
 }
 
+glib::wrapper! {
+    pub struct Lcms(ObjectSubclass<lcms::Lcms>) @extends gst_video::VideoFilter, gst_base::BaseTransform, gst::Element, gst::Object;
+}
+
 fn plugin_init(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
     gst::Element::register(
         Some(plugin),
         gllcms::GlLcms::NAME,
         gst::Rank::None,
         gllcms::GlLcms::type_(),
+    )?;
+    gst::Element::register(
+        Some(plugin),
+        lcms::Lcms::NAME,
+        gst::Rank::None,
+        lcms::Lcms::type_(),
     )
 }
@@ -0,0 +1,158 @@
+// Shared between `gllcms::GlLcms` (GPU) and `lcms::Lcms` (CPU fallback): the same knobs drive
+// the same lcms2 `Transform` construction regardless of which element applies it.
+
+use gst_gl::gst::glib;
+use lcms2::*;
+
+pub(crate) const DEFAULT_BRIGHTNESS: f64 = 0f64;
+pub(crate) const DEFAULT_CONTRAST: f64 = 1f64;
+pub(crate) const DEFAULT_HUE: f64 = 0f64;
+pub(crate) const DEFAULT_SATURATION: f64 = 0f64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Settings {
+    pub(crate) icc: Option<String>,
+    pub(crate) brightness: f64,
+    pub(crate) contrast: f64,
+    pub(crate) hue: f64,
+    pub(crate) saturation: f64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            icc: None,
+            brightness: DEFAULT_BRIGHTNESS,
+            contrast: DEFAULT_CONTRAST,
+            hue: DEFAULT_HUE,
+            saturation: DEFAULT_SATURATION,
+        }
+    }
+}
+
+// The `icc`/`brightness`/`contrast`/`hue`/`saturation` property specs, identical on both
+// elements; callers append whatever is specific to them (e.g. GL's read-only timing stat).
+pub(crate) fn color_param_specs() -> [glib::ParamSpec; 5] {
+    [
+        glib::ParamSpecString::builder("icc")
+            .nick("ICC Profile")
+            .blurb("Path to ICC color profile")
+            .build(),
+        glib::ParamSpecDouble::builder("brightness")
+            .nick("Bright")
+            .blurb("Extra brightness correction")
+            // TODO: Docs don't clarify min and max!
+            .minimum(f64::MIN)
+            .maximum(f64::MAX)
+            .default_value(DEFAULT_BRIGHTNESS)
+            .build(),
+        glib::ParamSpecDouble::builder("contrast")
+            .nick("Contrast")
+            .blurb("Extra contrast correction")
+            // TODO: Docs don't clarify min and max!
+            .minimum(f64::MIN)
+            .maximum(f64::MAX)
+            .default_value(DEFAULT_CONTRAST)
+            .build(),
+        glib::ParamSpecDouble::builder("hue")
+            .nick("Hue")
+            .blurb("Extra hue displacement in degrees")
+            .minimum(0f64)
+            .maximum(360f64)
+            .default_value(DEFAULT_HUE)
+            .build(),
+        glib::ParamSpecDouble::builder("saturation")
+            .nick("Saturation")
+            .blurb("Extra saturation correction")
+            // TODO: Docs don't clarify min and max!
+            .minimum(f64::MIN)
+            .maximum(f64::MAX)
+            .default_value(DEFAULT_SATURATION)
+            .build(),
+    ]
+}
+
+pub(crate) fn set_color_property(
+    settings: &mut Settings,
+    pspec: &glib::ParamSpec,
+    value: &glib::Value,
+) -> bool {
+    match pspec.name() {
+        "icc" => settings.icc = value.get().expect("Type mismatch"),
+        "brightness" => settings.brightness = value.get().expect("Type mismatch"),
+        "contrast" => settings.contrast = value.get().expect("Type mismatch"),
+        "hue" => settings.hue = value.get().expect("Type mismatch"),
+        "saturation" => settings.saturation = value.get().expect("Type mismatch"),
+        _ => return false,
+    }
+    true
+}
+
+pub(crate) fn color_property(settings: &Settings, pspec: &glib::ParamSpec) -> Option<glib::Value> {
+    Some(match pspec.name() {
+        "icc" => settings.icc.to_value(),
+        "brightness" => settings.brightness.to_value(),
+        "contrast" => settings.contrast.to_value(),
+        "hue" => settings.hue.to_value(),
+        "saturation" => settings.saturation.to_value(),
+        _ => return None,
+    })
+}
+
+// Builds the exact lcms2 `Transform` both elements need: the optional ICC profile, then the
+// brightness/contrast/hue/saturation abstract profile, with sRGB as the output profile last in
+// the chain. `format` is used for both the input and output pixel format, since neither element
+// changes the pixel layout, only the colour values.
+pub(crate) fn build_transform(settings: &Settings, format: PixelFormat) -> Transform<u32, u32> {
+    let mut profiles = vec![];
+
+    if let Some(icc) = &settings.icc {
+        let custom_profile = Profile::new_file(icc).unwrap();
+        profiles.push(custom_profile);
+    }
+
+    // TODO: Put these four settings in a separate struct for easy Default comparison and elision
+    let bcsh = Profile::new_bchsw_abstract_context(
+        GlobalContext::new(),
+        // Can't have more than 255 points... Is this per-axis (as it's rather slow)?
+        255,
+        settings.brightness,
+        settings.contrast,
+        settings.hue,
+        settings.saturation,
+        /* No color temperature support yet */ None,
+    )
+    .unwrap();
+    profiles.push(bcsh);
+
+    // Use sRGB as output profile, last in the chain
+    let output_profile = Profile::new_srgb();
+
+    // TODO: bcsh on its own breaks Transform construction
+
+    if let [single_profile] = &profiles[..] {
+        Transform::new(
+            single_profile,
+            format,
+            &output_profile,
+            format,
+            Intent::Perceptual,
+        )
+        .unwrap()
+    } else {
+        // Output profile is last in the chain
+        profiles.push(output_profile);
+
+        // Turn into vec of references
+        let profiles = profiles.iter().collect::<Vec<_>>();
+        Transform::new_multiprofile(
+            &profiles,
+            format,
+            format,
+            Intent::Perceptual,
+            // TODO: Check all flags
+            Flags::NO_NEGATIVES | Flags::KEEP_SEQUENCE,
+        )
+        .unwrap()
+    }
+}
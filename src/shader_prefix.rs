@@ -0,0 +1,17 @@
+// Shared between the runtime GLSL fallback path (`create_shader` in gllcms.rs) and build.rs's
+// build-time SPIR-V pre-validation, via `include!`, so the two can never drift apart.
+
+// Size of one axis of the 3D LUT grid. Trades accuracy for upload size/transform cost;
+// 33 is the common "web-safe" grid size used by most color-grading LUT formats.
+pub(crate) const LUT_SIZE: u32 = 33;
+
+// Builds the `#version`/`#define` prefix prepended to the fragment shader before compilation.
+// `version` is the bare GLSL version string (e.g. "430"), without the `#version` keyword.
+pub(crate) fn fragment_prefix(version: &str) -> String {
+    format!("#version {version}\n#define LUT_SIZE {LUT_SIZE}.0\n")
+}
+
+// Same as `fragment_prefix`, but the vertex stage doesn't need `LUT_SIZE`.
+pub(crate) fn vertex_prefix(version: &str) -> String {
+    format!("#version {version}\n")
+}